@@ -3,16 +3,36 @@
  * 
  * On-chain lease management with:
  * - Program Derived Addresses (PDAs) for lease accounts
- * - Multi-signature verification (manager + tenant)
+ * - Multi-signature verification (manager + m-of-n tenant-side signers) backed by real Ed25519 checks
  * - Atomic lease activation
- * - Security deposit escrow (future)
+ * - Security deposit escrow (SPL token vault released via a conditional
+ *   Plan: timestamp-gated payout to the manager, or an early refund to the
+ *   tenant witnessed by the manager's signature)
+ * - Recurring monthly rent ledger with late-fee accrual
  * - Lease status management
+ * - Lifecycle events and an on-chain typed status-change audit trail
  */
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
 declare_id!("RentF1ow11111111111111111111111111111111111");
 
+/// Rent is billed on a 30-day cadence.
+const SECONDS_PER_RENT_PERIOD: i64 = 30 * 24 * 60 * 60;
+/// Caps `RentLedger::payments` at ~10 years of monthly history.
+const RENT_LEDGER_CAPACITY: usize = 120;
+/// Caps the tenant-side multisig at `Lease::signers[0]` (primary tenant)
+/// plus up to 7 co-tenants/guarantors.
+const MAX_TENANT_SIGNERS: usize = 8;
+/// Caps `Lease::history`; a lease's status only ever moves
+/// Pending -> Active -> {Terminated, Completed}, so this is generous headroom.
+const MAX_HISTORY_LEN: usize = 16;
+
 #[program]
 pub mod rentflow_core {
     use super::*;
@@ -27,11 +47,14 @@ pub mod rentflow_core {
         ctx: Context<InitializeLease>,
         lease_id: String,
         lease_hash: [u8; 32],
-        tenant_wallet: Pubkey,
+        signers: Vec<Pubkey>,
+        threshold: u8,
         monthly_rent: u64,
         security_deposit: u64,
         start_date: i64,
         end_date: i64,
+        late_fee_bps: u16,
+        grace_period: i64,
     ) -> Result<()> {
         require!(
             lease_id.len() <= 64,
@@ -45,6 +68,36 @@ pub mod rentflow_core {
             end_date > start_date,
             LeaseError::InvalidDateRange
         );
+        require!(
+            late_fee_bps <= 10_000,
+            LeaseError::InvalidLateFeeBps
+        );
+        require!(!signers.is_empty(), LeaseError::NoTenantSigners);
+        require!(
+            signers.len() <= MAX_TENANT_SIGNERS,
+            LeaseError::TooManySigners
+        );
+        require!(
+            threshold >= 1 && threshold as usize <= signers.len(),
+            LeaseError::InvalidThreshold
+        );
+        // Duplicate entries would make the later slot permanently unreachable
+        // in `sign_lease` (it resolves a signer to a slot via `position`,
+        // i.e. first match only), silently shrinking the effective multisig
+        // below `threshold`.
+        for i in 0..signers.len() {
+            for j in (i + 1)..signers.len() {
+                require!(signers[i] != signers[j], LeaseError::DuplicateSigner);
+            }
+        }
+        // The manager's key always wins the `signer == lease.manager_wallet`
+        // branch in `sign_lease`, so a manager key placed in `signers` could
+        // never have its slot marked signed, permanently wedging the lease
+        // in `Pending` if `threshold == signers.len()`.
+        require!(
+            signers.iter().all(|s| s != &ctx.accounts.manager.key()),
+            LeaseError::DuplicateSigner
+        );
 
         let lease = &mut ctx.accounts.lease;
         let clock = Clock::get()?;
@@ -52,75 +105,122 @@ pub mod rentflow_core {
         lease.lease_id = lease_id;
         lease.lease_hash = lease_hash;
         lease.manager_wallet = ctx.accounts.manager.key();
-        lease.tenant_wallet = tenant_wallet;
+        lease.mint = ctx.accounts.mint.key();
+        lease.signed = vec![false; signers.len()];
+        lease.signatures = vec![[0u8; 64]; signers.len()];
+        lease.signers = signers;
+        lease.threshold = threshold;
         lease.monthly_rent = monthly_rent;
         lease.security_deposit = security_deposit;
         lease.start_date = start_date;
         lease.end_date = end_date;
         lease.manager_signed = false;
-        lease.tenant_signed = false;
-        lease.manager_signature = [0; 32];
-        lease.tenant_signature = [0; 32];
+        lease.manager_signature = [0; 64];
         lease.status = LeaseStatus::Pending;
         lease.created_at = clock.unix_timestamp;
         lease.activated_at = 0;
+        lease.deposit_paid = false;
+        lease.plan = None;
+        lease.history = Vec::new();
         lease.bump = ctx.bumps.lease;
+        lease.vault_bump = ctx.bumps.vault;
+
+        let rent_ledger = &mut ctx.accounts.rent_ledger;
+        rent_ledger.lease = lease.key();
+        rent_ledger.payments = Vec::new();
+        rent_ledger.next_due_date = start_date;
+        rent_ledger.late_fee_bps = late_fee_bps;
+        rent_ledger.grace_period = grace_period;
+        rent_ledger.bump = ctx.bumps.rent_ledger;
 
         msg!("✅ Lease initialized: {}", lease.lease_id);
         msg!("   Manager: {}", lease.manager_wallet);
-        msg!("   Tenant: {}", lease.tenant_wallet);
+        msg!("   Tenant-side signers: {}", lease.signers.len());
+        msg!("   Threshold: {}", lease.threshold);
         msg!("   Monthly Rent: {} USDC", monthly_rent);
 
+        emit!(LeaseCreated {
+            lease_id: lease.lease_id.clone(),
+            manager: lease.manager_wallet,
+            tenant: lease.signers[0],
+            monthly_rent,
+            timestamp: lease.created_at,
+        });
+
         Ok(())
     }
 
     /**
-     * Sign the lease (manager or tenant)
-     * 
-     * Records cryptographic signature from signer
-     * Auto-activates lease when both parties have signed
+     * Sign the lease (manager, or a tenant-side signer)
+     *
+     * Verifies a preceding Ed25519 native-program instruction actually
+     * signed `lease_hash` with the caller's pubkey, then records the
+     * attested 64-byte signature against the caller's slot. Auto-activates
+     * the lease once the manager plus at least `threshold` tenant-side
+     * signers have signed — the classic manager+tenant lease is just the
+     * `threshold = 1`, single-signer case.
      */
-    pub fn sign_lease(
-        ctx: Context<SignLease>,
-        signature_hash: [u8; 32],
-    ) -> Result<()> {
-        let lease = &mut ctx.accounts.lease;
+    pub fn sign_lease(ctx: Context<SignLease>) -> Result<()> {
         let signer = ctx.accounts.signer.key();
-        let clock = Clock::get()?;
 
         require!(
-            lease.status == LeaseStatus::Pending,
+            ctx.accounts.lease.status == LeaseStatus::Pending,
             LeaseError::LeaseNotPending
         );
 
+        let lease_hash = ctx.accounts.lease.lease_hash;
+        let signature = verify_ed25519_instruction(
+            &ctx.accounts.instructions.to_account_info(),
+            &signer,
+            &lease_hash,
+        )?;
+
+        let lease = &mut ctx.accounts.lease;
+        let clock = Clock::get()?;
+
         // Determine which party is signing
-        if signer == lease.manager_wallet {
+        let signer_type = if signer == lease.manager_wallet {
             require!(
                 !lease.manager_signed,
                 LeaseError::AlreadySigned
             );
             lease.manager_signed = true;
-            lease.manager_signature = signature_hash;
+            lease.manager_signature = signature;
             msg!("✅ Manager signed lease: {}", lease.lease_id);
-        } else if signer == lease.tenant_wallet {
-            require!(
-                !lease.tenant_signed,
-                LeaseError::AlreadySigned
-            );
-            lease.tenant_signed = true;
-            lease.tenant_signature = signature_hash;
-            msg!("✅ Tenant signed lease: {}", lease.lease_id);
+            "manager"
+        } else if let Some(idx) = lease.signers.iter().position(|s| s == &signer) {
+            require!(!lease.signed[idx], LeaseError::AlreadySigned);
+            lease.signed[idx] = true;
+            lease.signatures[idx] = signature;
+            msg!("✅ Tenant-side signer {} signed lease: {}", idx, lease.lease_id);
+            "tenant"
         } else {
             return Err(LeaseError::UnauthorizedSigner.into());
-        }
+        };
+
+        emit!(LeaseSigned {
+            lease_id: lease.lease_id.clone(),
+            signer,
+            signer_type: signer_type.to_string(),
+            timestamp: clock.unix_timestamp,
+        });
 
-        // Auto-activate if both parties have signed
-        if lease.manager_signed && lease.tenant_signed {
+        // Auto-activate once the manager and at least `threshold` tenant-side
+        // signers have signed
+        let satisfied = lease.signed.iter().filter(|s| **s).count() as u8;
+        if lease.manager_signed && satisfied >= lease.threshold {
+            let old_status = lease.status.clone();
             lease.status = LeaseStatus::Active;
             lease.activated_at = clock.unix_timestamp;
-            msg!("🎉 Lease activated! Both parties signed.");
+            record_status_change(lease, old_status, LeaseStatus::Active, signer, lease.activated_at)?;
+            msg!("🎉 Lease activated! Signature threshold met.");
             msg!("   Lease ID: {}", lease.lease_id);
             msg!("   Activated at: {}", lease.activated_at);
+
+            emit!(LeaseActivated {
+                lease_id: lease.lease_id.clone(),
+                timestamp: lease.activated_at,
+            });
         }
 
         Ok(())
@@ -140,9 +240,9 @@ pub mod rentflow_core {
         let signer = ctx.accounts.signer.key();
         let clock = Clock::get()?;
 
-        // Only manager or tenant can update status
+        // Only the manager or a tenant-side signer can update status
         require!(
-            signer == lease.manager_wallet || signer == lease.tenant_wallet,
+            signer == lease.manager_wallet || lease.signers.contains(&signer),
             LeaseError::UnauthorizedSigner
         );
 
@@ -165,33 +265,408 @@ pub mod rentflow_core {
             }
         }
 
-        lease.status = new_status;
+        let old_status = lease.status.clone();
+        lease.status = new_status.clone();
+        record_status_change(lease, old_status.clone(), new_status.clone(), signer, clock.unix_timestamp)?;
+
+        emit!(LeaseStatusChanged {
+            lease_id: lease.lease_id.clone(),
+            old_status,
+            new_status,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
     /**
      * Verify lease signatures
-     * 
-     * Public read-only function to verify both signatures exist
+     *
+     * Public read-only function confirming the manager signed, that the
+     * tenant-side multisig threshold is satisfied, and that every recorded
+     * signature is actually present (not left blank by a partial signing).
+     * Reports the satisfied/required tenant-side signature counts so
+     * callers can render multisig progress.
      */
     pub fn verify_lease(
         ctx: Context<VerifyLease>,
-    ) -> Result<bool> {
+    ) -> Result<VerificationStatus> {
         let lease = &ctx.accounts.lease;
-        
-        let is_valid = lease.manager_signed 
-            && lease.tenant_signed 
+
+        let manager_signature_recorded = lease.manager_signature != [0u8; 64];
+        let signatures_satisfied = lease
+            .signed
+            .iter()
+            .zip(lease.signatures.iter())
+            .filter(|(signed, signature)| **signed && **signature != [0u8; 64])
+            .count() as u8;
+
+        let is_valid = lease.manager_signed
+            && manager_signature_recorded
+            && signatures_satisfied >= lease.threshold
             && lease.status == LeaseStatus::Active;
 
         msg!("Lease verification: {}", is_valid);
         msg!("  Manager signed: {}", lease.manager_signed);
-        msg!("  Tenant signed: {}", lease.tenant_signed);
+        msg!("  Tenant-side signatures: {}/{}", signatures_satisfied, lease.threshold);
         msg!("  Status: {:?}", lease.status);
 
-        Ok(is_valid)
+        Ok(VerificationStatus {
+            is_valid,
+            manager_signed: lease.manager_signed,
+            signatures_satisfied,
+            signatures_required: lease.threshold,
+        })
+    }
+
+    /**
+     * Get a lease's status-change history
+     *
+     * Public read-only function returning the append-only audit trail
+     * recorded by `record_status_change` for every transition the lease
+     * has gone through so far.
+     */
+    pub fn get_history(ctx: Context<GetHistory>) -> Result<Vec<StatusEvent>> {
+        Ok(ctx.accounts.lease.history.clone())
+    }
+
+    /**
+     * Deposit the security deposit into the lease's escrow vault
+     *
+     * Tenant transfers `security_deposit` USDC into the vault PDA, then
+     * arms the conditional release plan: pay the manager once the lease
+     * end date passes, OR refund the tenant if the manager witnesses an
+     * early refund with their signature.
+     */
+    pub fn deposit_security_deposit(ctx: Context<DepositSecurityDeposit>) -> Result<()> {
+        let lease = &ctx.accounts.lease;
+
+        require!(!lease.deposit_paid, LeaseError::DepositAlreadyPaid);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.tenant_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.tenant.to_account_info(),
+                },
+            ),
+            lease.security_deposit,
+        )?;
+
+        let lease = &mut ctx.accounts.lease;
+        lease.deposit_paid = true;
+        lease.plan = Some(Plan::Or(
+            (
+                Condition::Timestamp(lease.end_date),
+                Action::Pay {
+                    amount: lease.security_deposit,
+                    to: lease.manager_wallet,
+                },
+            ),
+            (
+                Condition::Signature(lease.manager_wallet),
+                Action::Pay {
+                    amount: lease.security_deposit,
+                    to: lease.signers[0],
+                },
+            ),
+        ));
+
+        msg!("💰 Security deposit escrowed: {}", lease.lease_id);
+
+        Ok(())
+    }
+
+    /**
+     * Witness a condition against the lease's active escrow plan
+     *
+     * Feeds a timestamp witness (the current `Clock`) and a signature
+     * witness (the caller's pubkey) into `process_event`. If either
+     * satisfies a branch of the plan, the plan is consumed and its
+     * `Action::Pay` fires via a token CPI from the vault.
+     */
+    pub fn witness(ctx: Context<Witness>) -> Result<()> {
+        require!(ctx.accounts.lease.plan.is_some(), LeaseError::NoActivePlan);
+
+        let clock = Clock::get()?;
+        let timestamp_event = Condition::Timestamp(clock.unix_timestamp);
+        let signature_event = Condition::Signature(ctx.accounts.signer.key());
+
+        let mut plan = ctx.accounts.lease.plan.clone();
+        let action = process_event(&mut plan, &timestamp_event)
+            .or_else(|| process_event(&mut plan, &signature_event));
+        ctx.accounts.lease.plan = plan;
+
+        let action = action.ok_or(LeaseError::PlanConditionNotMet)?;
+        let (amount, to) = match action {
+            Action::Pay { amount, to } => (amount, to),
+        };
+
+        require!(
+            ctx.accounts.payee_token_account.owner == to,
+            LeaseError::InvalidPayee
+        );
+
+        let lease_id = ctx.accounts.lease.lease_id.clone();
+        let bump = ctx.accounts.lease.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lease", lease_id.as_bytes(), &[bump]]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.payee_token_account.to_account_info(),
+                    authority: ctx.accounts.lease.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        emit!(PlanExecuted {
+            lease_id,
+            to,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("📜 Plan executed for lease: {}", ctx.accounts.lease.lease_id);
+
+        Ok(())
+    }
+
+    /**
+     * Pay the current period's rent
+     *
+     * Transfers `monthly_rent` USDC from tenant to manager, plus an
+     * on-chain-computed late fee once `next_due_date + grace_period` has
+     * passed, then records the payment and advances `next_due_date`.
+     */
+    pub fn pay_rent(ctx: Context<PayRent>) -> Result<()> {
+        require!(
+            ctx.accounts.lease.status == LeaseStatus::Active,
+            LeaseError::LeaseNotActive
+        );
+        require!(
+            ctx.accounts.rent_ledger.payments.len() < RENT_LEDGER_CAPACITY,
+            LeaseError::RentLedgerFull
+        );
+
+        let clock = Clock::get()?;
+        let late_fee = late_fee_due(
+            &ctx.accounts.lease,
+            &ctx.accounts.rent_ledger,
+            clock.unix_timestamp,
+        );
+        let amount_due = ctx.accounts.lease.monthly_rent + late_fee;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.tenant_token_account.to_account_info(),
+                    to: ctx.accounts.manager_token_account.to_account_info(),
+                    authority: ctx.accounts.tenant.to_account_info(),
+                },
+            ),
+            amount_due,
+        )?;
+
+        let period_index = ctx.accounts.rent_ledger.payments.len() as u32;
+        let lease_id = ctx.accounts.lease.lease_id.clone();
+
+        let rent_ledger = &mut ctx.accounts.rent_ledger;
+        rent_ledger.payments.push(RentPayment {
+            period_index,
+            amount_paid: ctx.accounts.lease.monthly_rent,
+            late_fee_paid: late_fee,
+            paid_at: clock.unix_timestamp,
+        });
+        rent_ledger.next_due_date += SECONDS_PER_RENT_PERIOD;
+
+        emit!(RentPaid {
+            lease_id,
+            period_index,
+            amount_paid: ctx.accounts.lease.monthly_rent,
+            late_fee_paid: late_fee,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("💵 Rent paid for period {}: {}", period_index, ctx.accounts.lease.lease_id);
+
+        Ok(())
+    }
+
+    /**
+     * Read-only rent status
+     *
+     * Reports periods paid, the balance currently due (including any
+     * accrued late fee), and whether the lease is delinquent.
+     */
+    pub fn rent_status(ctx: Context<RentStatus>) -> Result<RentStatusResponse> {
+        let clock = Clock::get()?;
+        let lease = &ctx.accounts.lease;
+        let rent_ledger = &ctx.accounts.rent_ledger;
+
+        let late_fee = late_fee_due(lease, rent_ledger, clock.unix_timestamp);
+        let is_delinquent = late_fee > 0;
+
+        Ok(RentStatusResponse {
+            periods_paid: rent_ledger.payments.len() as u32,
+            balance_due: lease.monthly_rent + late_fee,
+            next_due_date: rent_ledger.next_due_date,
+            is_delinquent,
+        })
     }
 }
 
+/// Computes the late fee owed, given the current time, or 0 if the tenant
+/// is still within `next_due_date + grace_period`.
+fn late_fee_due(lease: &Lease, rent_ledger: &RentLedger, now: i64) -> u64 {
+    if now <= rent_ledger.next_due_date + rent_ledger.grace_period {
+        return 0;
+    }
+
+    (lease.monthly_rent as u128 * rent_ledger.late_fee_bps as u128 / 10_000) as u64
+}
+
+/// Appends a `StatusEvent` to the lease's audit trail.
+fn record_status_change(
+    lease: &mut Lease,
+    from: LeaseStatus,
+    to: LeaseStatus,
+    actor: Pubkey,
+    at: i64,
+) -> Result<()> {
+    require!(
+        lease.history.len() < MAX_HISTORY_LEN,
+        LeaseError::HistoryFull
+    );
+    lease.history.push(StatusEvent { from, to, actor, at });
+    Ok(())
+}
+
+/// Checks whether a witnessed event satisfies a plan's condition.
+fn condition_met(condition: &Condition, event: &Condition) -> bool {
+    match (condition, event) {
+        (Condition::Timestamp(deadline), Condition::Timestamp(now)) => now >= deadline,
+        (Condition::Signature(required), Condition::Signature(observed)) => required == observed,
+        _ => false,
+    }
+}
+
+/// Mutates `plan` in response to a witnessed `event`, modeled on the old
+/// Solana budget program's `Budget::apply_event`. Returns the action to
+/// execute if a branch became satisfied, clearing the plan atomically so
+/// it cannot fire twice.
+fn process_event(plan: &mut Option<Plan>, event: &Condition) -> Option<Action> {
+    let action = match plan.as_ref()? {
+        Plan::After(condition, action) => condition_met(condition, event).then(|| action.clone()),
+        Plan::Or((cond_a, action_a), (cond_b, action_b)) => {
+            if condition_met(cond_a, event) {
+                Some(action_a.clone())
+            } else if condition_met(cond_b, event) {
+                Some(action_b.clone())
+            } else {
+                None
+            }
+        }
+    };
+
+    if action.is_some() {
+        *plan = None;
+    }
+
+    action
+}
+
+// ============ Signature Verification ============
+
+/// Ed25519SignatureOffsets, as laid out by the native Ed25519 program:
+/// https://docs.rs/solana-program/latest/solana_program/ed25519_program/
+const ED25519_SIGNATURE_OFFSET: usize = 2;
+const ED25519_SIGNATURE_INSTRUCTION_INDEX_OFFSET: usize = 4;
+const ED25519_PUBLIC_KEY_OFFSET: usize = 6;
+const ED25519_PUBLIC_KEY_INSTRUCTION_INDEX_OFFSET: usize = 8;
+const ED25519_MESSAGE_DATA_OFFSET: usize = 10;
+const ED25519_MESSAGE_DATA_SIZE_OFFSET: usize = 12;
+const ED25519_MESSAGE_INSTRUCTION_INDEX_OFFSET: usize = 14;
+/// Size of the fixed `Ed25519SignatureOffsets` header this function reads.
+const ED25519_OFFSETS_HEADER_LEN: usize = 16;
+/// Sentinel meaning "this instruction", per the native Ed25519 program.
+const ED25519_SELF_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+/// Confirms that the instruction immediately preceding this one in the
+/// transaction is a native Ed25519 program instruction attesting that
+/// `expected_signer` signed exactly `expected_message`, and returns the
+/// 64-byte signature it carries.
+fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    expected_signer: &Pubkey,
+    expected_message: &[u8],
+) -> Result<[u8; 64]> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, LeaseError::SignatureMismatch);
+
+    let ed25519_ix =
+        load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+
+    require!(
+        ed25519_ix.program_id == ed25519_program::ID,
+        LeaseError::SignatureMismatch
+    );
+
+    let data = &ed25519_ix.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_HEADER_LEN,
+        LeaseError::SignatureMismatch
+    );
+    require!(data[0] == 1, LeaseError::SignatureMismatch); // exactly one signature
+
+    let read_u16 = |offset: usize| -> usize {
+        u16::from_le_bytes([data[offset], data[offset + 1]]) as usize
+    };
+
+    // Each offset field can otherwise point at an arbitrary other instruction
+    // in the transaction, letting an attacker mix a genuine signature/pubkey
+    // captured elsewhere with a forged message. Require everything to be
+    // self-referencing before trusting any offset below.
+    require!(
+        read_u16(ED25519_SIGNATURE_INSTRUCTION_INDEX_OFFSET) == ED25519_SELF_INSTRUCTION_INDEX as usize
+            && read_u16(ED25519_PUBLIC_KEY_INSTRUCTION_INDEX_OFFSET) == ED25519_SELF_INSTRUCTION_INDEX as usize
+            && read_u16(ED25519_MESSAGE_INSTRUCTION_INDEX_OFFSET) == ED25519_SELF_INSTRUCTION_INDEX as usize,
+        LeaseError::SignatureMismatch
+    );
+
+    let sig_offset = read_u16(ED25519_SIGNATURE_OFFSET);
+    let pubkey_offset = read_u16(ED25519_PUBLIC_KEY_OFFSET);
+    let message_offset = read_u16(ED25519_MESSAGE_DATA_OFFSET);
+    let message_size = read_u16(ED25519_MESSAGE_DATA_SIZE_OFFSET);
+
+    let signature: [u8; 64] = data
+        .get(sig_offset..sig_offset + 64)
+        .and_then(|s| s.try_into().ok())
+        .ok_or(LeaseError::SignatureMismatch)?;
+    let pubkey: [u8; 32] = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .and_then(|s| s.try_into().ok())
+        .ok_or(LeaseError::SignatureMismatch)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(LeaseError::SignatureMismatch)?;
+
+    require!(
+        pubkey == expected_signer.to_bytes(),
+        LeaseError::SignatureMismatch
+    );
+    require!(message == expected_message, LeaseError::SignatureMismatch);
+
+    Ok(signature)
+}
+
 // ============ Account Structures ============
 
 #[derive(Accounts)]
@@ -205,10 +680,32 @@ pub struct InitializeLease<'info> {
         bump
     )]
     pub lease: Account<'info, Lease>,
-    
+
+    #[account(
+        init,
+        payer = manager,
+        seeds = [b"vault", lease_id.as_bytes()],
+        bump,
+        token::mint = mint,
+        token::authority = lease,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = manager,
+        space = 8 + RentLedger::INIT_SPACE,
+        seeds = [b"rent_ledger", lease_id.as_bytes()],
+        bump
+    )]
+    pub rent_ledger: Account<'info, RentLedger>,
+
+    pub mint: Account<'info, Mint>,
+
     #[account(mut)]
     pub manager: Signer<'info>,
-    
+
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -216,8 +713,14 @@ pub struct InitializeLease<'info> {
 pub struct SignLease<'info> {
     #[account(mut)]
     pub lease: Account<'info, Lease>,
-    
+
     pub signer: Signer<'info>,
+
+    /// Instructions sysvar, introspected to find the preceding Ed25519
+    /// native-program instruction that attests `signer`'s signature.
+    /// CHECK: validated by address against the sysvar's well-known id.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -233,6 +736,118 @@ pub struct VerifyLease<'info> {
     pub lease: Account<'info, Lease>,
 }
 
+#[derive(Accounts)]
+pub struct GetHistory<'info> {
+    pub lease: Account<'info, Lease>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSecurityDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"lease", lease.lease_id.as_bytes()],
+        bump = lease.bump,
+    )]
+    pub lease: Account<'info, Lease>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", lease.lease_id.as_bytes()],
+        bump = lease.vault_bump,
+        token::mint = lease.mint,
+        token::authority = lease,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = tenant_token_account.owner == tenant.key(),
+    )]
+    pub tenant_token_account: Account<'info, TokenAccount>,
+
+    #[account(constraint = lease.signers.contains(&tenant.key()) @ LeaseError::UnauthorizedSigner)]
+    pub tenant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Witness<'info> {
+    #[account(
+        mut,
+        seeds = [b"lease", lease.lease_id.as_bytes()],
+        bump = lease.bump,
+    )]
+    pub lease: Account<'info, Lease>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", lease.lease_id.as_bytes()],
+        bump = lease.vault_bump,
+        token::mint = lease.mint,
+        token::authority = lease,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// Token account of whichever party the satisfied `Action::Pay` names.
+    #[account(mut)]
+    pub payee_token_account: Account<'info, TokenAccount>,
+
+    pub signer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct PayRent<'info> {
+    #[account(
+        seeds = [b"lease", lease.lease_id.as_bytes()],
+        bump = lease.bump,
+    )]
+    pub lease: Account<'info, Lease>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_ledger", lease.lease_id.as_bytes()],
+        bump = rent_ledger.bump,
+    )]
+    pub rent_ledger: Account<'info, RentLedger>,
+
+    #[account(
+        mut,
+        constraint = tenant_token_account.owner == tenant.key(),
+        token::mint = lease.mint,
+    )]
+    pub tenant_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = manager_token_account.owner == lease.manager_wallet,
+        token::mint = lease.mint,
+    )]
+    pub manager_token_account: Account<'info, TokenAccount>,
+
+    #[account(constraint = lease.signers.contains(&tenant.key()) @ LeaseError::UnauthorizedSigner)]
+    pub tenant: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RentStatus<'info> {
+    #[account(
+        seeds = [b"lease", lease.lease_id.as_bytes()],
+        bump = lease.bump,
+    )]
+    pub lease: Account<'info, Lease>,
+
+    #[account(
+        seeds = [b"rent_ledger", lease.lease_id.as_bytes()],
+        bump = rent_ledger.bump,
+    )]
+    pub rent_ledger: Account<'info, RentLedger>,
+}
+
 // ============ Data Structures ============
 
 #[account]
@@ -242,19 +857,29 @@ pub struct Lease {
     pub lease_id: String,          // Unique identifier
     pub lease_hash: [u8; 32],      // SHA-256 hash of lease terms
     pub manager_wallet: Pubkey,     // Landlord/property manager
-    pub tenant_wallet: Pubkey,      // Tenant
+    pub mint: Pubkey,                // SPL token mint rent/deposits are denominated in
+    #[max_len(8)] // keep in sync with MAX_TENANT_SIGNERS
+    pub signers: Vec<Pubkey>,       // Tenant-side signers; signers[0] is the primary tenant
+    pub threshold: u8,              // Tenant-side signatures required to activate
     pub monthly_rent: u64,          // Rent in USDC (6 decimals)
     pub security_deposit: u64,      // Security deposit in USDC
     pub start_date: i64,            // Unix timestamp
     pub end_date: i64,              // Unix timestamp
     pub manager_signed: bool,       // Manager signature status
-    pub tenant_signed: bool,        // Tenant signature status
-    pub manager_signature: [u8; 32], // Manager's signature hash
-    pub tenant_signature: [u8; 32],  // Tenant's signature hash
+    #[max_len(8)] // keep in sync with MAX_TENANT_SIGNERS
+    pub signed: Vec<bool>,          // Per-signer signature status, parallel to `signers`
+    pub manager_signature: [u8; 64], // Manager's Ed25519 signature over lease_hash
+    #[max_len(8)] // keep in sync with MAX_TENANT_SIGNERS
+    pub signatures: Vec<[u8; 64]>,  // Per-signer Ed25519 signatures, parallel to `signers`
     pub status: LeaseStatus,        // Current lease status
     pub created_at: i64,            // Creation timestamp
     pub activated_at: i64,          // Activation timestamp
+    pub deposit_paid: bool,         // Security deposit escrowed in vault
+    pub plan: Option<Plan>,         // Active conditional escrow-release plan
+    #[max_len(16)] // keep in sync with MAX_HISTORY_LEN
+    pub history: Vec<StatusEvent>,  // Append-only audit trail of status transitions
     pub bump: u8,                   // PDA bump seed
+    pub vault_bump: u8,             // Deposit vault PDA bump seed
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
@@ -265,6 +890,81 @@ pub enum LeaseStatus {
     Completed,    // Ended normally
 }
 
+/// One entry in a lease's status-change audit trail.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct StatusEvent {
+    pub from: LeaseStatus,  // Status before the transition
+    pub to: LeaseStatus,    // Status after the transition
+    pub actor: Pubkey,      // Who triggered the transition
+    pub at: i64,            // Unix timestamp of the transition
+}
+
+// ============ Rent Ledger ============
+
+#[account]
+#[derive(InitSpace)]
+pub struct RentLedger {
+    pub lease: Pubkey,              // Parent lease PDA
+    #[max_len(120)] // keep in sync with RENT_LEDGER_CAPACITY
+    pub payments: Vec<RentPayment>, // Payment history, one entry per period
+    pub next_due_date: i64,         // Unix timestamp the next payment is due
+    pub late_fee_bps: u16,          // Late fee, in basis points of monthly_rent
+    pub grace_period: i64,          // Seconds past next_due_date before a late fee accrues
+    pub bump: u8,                   // PDA bump seed
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RentPayment {
+    pub period_index: u32,
+    pub amount_paid: u64,
+    pub late_fee_paid: u64,
+    pub paid_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RentStatusResponse {
+    pub periods_paid: u32,
+    pub balance_due: u64,
+    pub next_due_date: i64,
+    pub is_delinquent: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VerificationStatus {
+    pub is_valid: bool,
+    pub manager_signed: bool,
+    pub signatures_satisfied: u8,
+    pub signatures_required: u8,
+}
+
+// ============ Conditional Escrow Engine ============
+//
+// Modeled on the original Solana budget program's Plan/Condition/Action
+// primitives: a `Plan` is witnessed with observed events until one of its
+// conditions is met, at which point the attached `Action` fires and the
+// plan is consumed.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum Condition {
+    /// Satisfied once `Clock::get()?.unix_timestamp` reaches this deadline.
+    Timestamp(i64),
+    /// Satisfied once this pubkey is observed as a witnessing signer.
+    Signature(Pubkey),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum Action {
+    Pay { amount: u64, to: Pubkey },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum Plan {
+    /// Fires `Action` once `Condition` is met.
+    After(Condition, Action),
+    /// Fires whichever `Action` has its paired `Condition` met first.
+    Or((Condition, Action), (Condition, Action)),
+}
+
 // ============ Error Codes ============
 
 #[error_code]
@@ -292,6 +992,45 @@ pub enum LeaseError {
     
     #[msg("Invalid status transition")]
     InvalidStatusTransition,
+
+    #[msg("Security deposit has already been escrowed")]
+    DepositAlreadyPaid,
+
+    #[msg("Lease has no active escrow plan")]
+    NoActivePlan,
+
+    #[msg("Witnessed event did not satisfy any branch of the plan")]
+    PlanConditionNotMet,
+
+    #[msg("Payee token account does not belong to the plan's designated payee")]
+    InvalidPayee,
+
+    #[msg("No valid Ed25519 signature over lease_hash from this signer")]
+    SignatureMismatch,
+
+    #[msg("Late fee must not exceed 10000 basis points")]
+    InvalidLateFeeBps,
+
+    #[msg("Lease is not active")]
+    LeaseNotActive,
+
+    #[msg("Rent ledger has reached its payment history capacity")]
+    RentLedgerFull,
+
+    #[msg("At least one tenant-side signer is required")]
+    NoTenantSigners,
+
+    #[msg("Too many tenant-side signers")]
+    TooManySigners,
+
+    #[msg("Threshold must be between 1 and the number of tenant-side signers")]
+    InvalidThreshold,
+
+    #[msg("Status history has reached its capacity")]
+    HistoryFull,
+
+    #[msg("Tenant-side signers must be unique")]
+    DuplicateSigner,
 }
 
 // ============ Events ============
@@ -326,3 +1065,20 @@ pub struct LeaseStatusChanged {
     pub new_status: LeaseStatus,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct PlanExecuted {
+    pub lease_id: String,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RentPaid {
+    pub lease_id: String,
+    pub period_index: u32,
+    pub amount_paid: u64,
+    pub late_fee_paid: u64,
+    pub timestamp: i64,
+}